@@ -3,19 +3,104 @@
 //! Uses the CoreAudio framework to control the default audio output device's
 //! volume and mute state via AudioObject property APIs.
 
-use super::{AudioControlError, SystemAudioControl};
+use super::{AudioControlError, AudioDevice, Scope, SystemAudioControl};
 use objc2_core_audio::{
-    kAudioDevicePropertyMute, kAudioDevicePropertyScopeOutput, kAudioDevicePropertyVolumeScalar,
-    kAudioHardwarePropertyDefaultOutputDevice, kAudioObjectPropertyElementMain,
-    kAudioObjectPropertyScopeGlobal, kAudioObjectSystemObject, AudioObjectGetPropertyData,
-    AudioObjectGetPropertyDataSize, AudioObjectPropertyAddress, AudioObjectSetPropertyData,
+    kAudioDevicePropertyDeviceIsAlive, kAudioDevicePropertyDeviceNameCFString,
+    kAudioDevicePropertyMute, kAudioDevicePropertyScopeInput, kAudioDevicePropertyScopeOutput,
+    kAudioDevicePropertyStreamConfiguration, kAudioDevicePropertyVolumeScalar,
+    kAudioHardwarePropertyDefaultInputDevice, kAudioHardwarePropertyDefaultOutputDevice,
+    kAudioHardwarePropertyDevices, kAudioObjectPropertyElementMain,
+    kAudioObjectPropertyScopeGlobal, kAudioObjectSystemObject, AudioBufferList,
+    AudioObjectAddPropertyListener, AudioObjectGetPropertyData, AudioObjectGetPropertyDataSize,
+    AudioObjectPropertyAddress, AudioObjectRemovePropertyListener, AudioObjectSetPropertyData,
 };
 use std::ffi::c_void;
 use std::ptr::NonNull;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+#[link(name = "CoreFoundation", kind = "framework")]
+extern "C" {
+    fn CFStringGetCString(
+        the_string: *const c_void,
+        buffer: *mut i8,
+        buffer_size: isize,
+        encoding: u32,
+    ) -> u8;
+    fn CFRelease(cf: *const c_void);
+}
+
+/// `kCFStringEncodingUTF8` from `CFString.h`.
+const K_CF_STRING_ENCODING_UTF8: u32 = 0x0800_0100;
+
+impl Scope {
+    /// CoreAudio selector for "the default device" in this scope.
+    fn default_device_selector(self) -> u32 {
+        match self {
+            Scope::Output => kAudioHardwarePropertyDefaultOutputDevice,
+            Scope::Input => kAudioHardwarePropertyDefaultInputDevice,
+        }
+    }
+
+    /// CoreAudio property scope for per-device properties (volume, mute, ...).
+    fn property_scope(self) -> u32 {
+        match self {
+            Scope::Output => kAudioDevicePropertyScopeOutput,
+            Scope::Input => kAudioDevicePropertyScopeInput,
+        }
+    }
+
+    /// Property address watched for default-device changes in this scope.
+    fn default_device_address(self) -> AudioObjectPropertyAddress {
+        AudioObjectPropertyAddress {
+            mSelector: self.default_device_selector(),
+            mScope: kAudioObjectPropertyScopeGlobal,
+            mElement: kAudioObjectPropertyElementMain,
+        }
+    }
+}
+
+/// Shared state read by the property-listener callback: which scope we're
+/// watching and the device id it should keep fresh.
+struct DeviceWatch {
+    device_id: AtomicU32,
+    scope: Scope,
+    /// If true, `device_id` is a user-pinned device (from [`list_devices`])
+    /// and must not be overridden by default-device changes.
+    pinned: bool,
+}
+
+/// Property-listener callback invoked by CoreAudio when the default device
+/// (in whichever scope it was registered for) changes, e.g. headphones
+/// plugged in. `client_data` is the raw pointer produced by `Arc::into_raw`
+/// for the controller's shared [`DeviceWatch`].
+unsafe extern "C" fn default_device_listener(
+    _object_id: u32,
+    _num_addresses: u32,
+    _addresses: *const AudioObjectPropertyAddress,
+    client_data: *mut c_void,
+) -> i32 {
+    let watch = unsafe { &*(client_data as *const DeviceWatch) };
+    if let Ok(new_device) = MacOSAudioController::get_default_device(watch.scope) {
+        watch.device_id.store(new_device, Ordering::SeqCst);
+        log::info!(
+            "Default {:?} device changed, now tracking device {}",
+            watch.scope,
+            new_device
+        );
+    }
+    0
+}
 
 /// macOS audio controller using CoreAudio.
 pub struct MacOSAudioController {
-    device_id: u32,
+    /// Cached device ID: the default device, kept fresh by a CoreAudio
+    /// property listener, or a pinned device (no listener). `Arc`-wrapped so the listener callback can hold a stable
+    /// pointer to it independent of where this struct itself lives.
+    watch: Arc<DeviceWatch>,
+    /// Whether `AudioObjectAddPropertyListener` actually succeeded, so `Drop`
+    /// knows whether there's a listener (and a leaked `Arc` clone) to undo.
+    listener_registered: bool,
 }
 
 // SAFETY: CoreAudio APIs are thread-safe
@@ -23,18 +108,136 @@ unsafe impl Send for MacOSAudioController {}
 unsafe impl Sync for MacOSAudioController {}
 
 impl MacOSAudioController {
-    /// Create a new macOS audio controller.
+    /// Create a new macOS audio controller for the default output device.
     ///
-    /// Gets the default output device ID for subsequent operations.
+    /// Gets the default output device ID for subsequent operations and
+    /// registers a listener to keep it current if the default device changes.
     pub fn new() -> Result<Self, AudioControlError> {
-        let device_id = Self::get_default_output_device()?;
-        Ok(Self { device_id })
+        Self::with_scope(Scope::Output)
+    }
+
+    /// Create a new macOS audio controller for the given [`Scope`].
+    ///
+    /// Gets the current default device ID for subsequent operations and
+    /// registers a listener to keep it current if the default device changes.
+    pub fn with_scope(scope: Scope) -> Result<Self, AudioControlError> {
+        Self::with_device(scope, None)
+    }
+
+    /// Create a new macOS audio controller for the given [`Scope`], optionally
+    /// pinned to a specific `device_id` (from [`list_devices`]) instead of
+    /// always following the default device.
+    ///
+    /// When no `device_id` is given, a listener is registered to keep the
+    /// cached device current if the default device changes.
+    pub fn with_device(scope: Scope, device_id: Option<u32>) -> Result<Self, AudioControlError> {
+        let pinned = device_id.is_some();
+        let resolved_id = match device_id {
+            Some(id) => id,
+            None => Self::get_default_device(scope)?,
+        };
+
+        let watch = Arc::new(DeviceWatch {
+            device_id: AtomicU32::new(resolved_id),
+            scope,
+            pinned,
+        });
+
+        let mut listener_registered = false;
+        if !pinned {
+            // Pass a clone's raw pointer as the listener's client data; it's
+            // reclaimed (and the listener removed) in `Drop`.
+            let client_data = Arc::into_raw(Arc::clone(&watch)) as *mut c_void;
+            let address = scope.default_device_address();
+            let status = unsafe {
+                AudioObjectAddPropertyListener(
+                    kAudioObjectSystemObject,
+                    NonNull::new(&address as *const _ as *mut _).unwrap(),
+                    Some(default_device_listener),
+                    client_data,
+                )
+            };
+            if status == 0 {
+                listener_registered = true;
+            } else {
+                // Reclaim the Arc clone we just leaked; `Drop` won't, since no
+                // listener was registered.
+                unsafe { drop(Arc::from_raw(client_data as *const DeviceWatch)) };
+                log::warn!(
+                    "Failed to register default device listener (OSStatus: {})",
+                    status
+                );
+            }
+        }
+
+        Ok(Self {
+            watch,
+            listener_registered,
+        })
     }
 
-    /// Get the default audio output device ID.
-    fn get_default_output_device() -> Result<u32, AudioControlError> {
+    /// Current cached device id.
+    fn current_device_id(&self) -> u32 {
+        self.watch.device_id.load(Ordering::SeqCst)
+    }
+
+    /// Check whether a device is still alive (e.g. not unplugged).
+    ///
+    /// `DeviceIsAlive` is a device-level property, not a per-direction one,
+    /// so it must be queried in the global scope rather than input/output.
+    fn device_is_alive(&self, device_id: u32) -> bool {
         let address = AudioObjectPropertyAddress {
-            mSelector: kAudioHardwarePropertyDefaultOutputDevice,
+            mSelector: kAudioDevicePropertyDeviceIsAlive,
+            mScope: kAudioObjectPropertyScopeGlobal,
+            mElement: kAudioObjectPropertyElementMain,
+        };
+
+        let mut value: u32 = 0;
+        let mut size = std::mem::size_of::<u32>() as u32;
+
+        let status = unsafe {
+            AudioObjectGetPropertyData(
+                device_id,
+                NonNull::new(&address as *const _ as *mut _).unwrap(),
+                0,
+                std::ptr::null(),
+                NonNull::new(&mut size as *mut _).unwrap(),
+                NonNull::new(&mut value as *mut _ as *mut c_void).unwrap(),
+            )
+        };
+
+        status == 0 && value != 0
+    }
+
+    /// Device id to use for the next operation, falling back to re-querying
+    /// the current default if the cached device has gone away. A pinned
+    /// (explicitly chosen) device is never silently swapped for the default.
+    fn active_device_id(&self) -> Result<u32, AudioControlError> {
+        let cached = self.current_device_id();
+        if self.device_is_alive(cached) {
+            return Ok(cached);
+        }
+
+        if self.watch.pinned {
+            return Err(AudioControlError::GetPropertyFailed(format!(
+                "Selected device {} is no longer available",
+                cached
+            )));
+        }
+
+        log::warn!(
+            "Cached audio device {} is no longer alive, re-querying default",
+            cached
+        );
+        let fresh = Self::get_default_device(self.watch.scope)?;
+        self.watch.device_id.store(fresh, Ordering::SeqCst);
+        Ok(fresh)
+    }
+
+    /// Get the current default device ID for the given scope.
+    fn get_default_device(scope: Scope) -> Result<u32, AudioControlError> {
+        let address = AudioObjectPropertyAddress {
+            mSelector: scope.default_device_selector(),
             mScope: kAudioObjectPropertyScopeGlobal,
             mElement: kAudioObjectPropertyElementMain,
         };
@@ -55,34 +258,36 @@ impl MacOSAudioController {
 
         if status != 0 {
             return Err(AudioControlError::InitializationFailed(format!(
-                "Failed to get default output device (OSStatus: {})",
-                status
+                "Failed to get default {:?} device (OSStatus: {})",
+                scope, status
             )));
         }
 
         if device_id == 0 {
-            return Err(AudioControlError::InitializationFailed(
-                "No default output device found".to_string(),
-            ));
+            return Err(AudioControlError::InitializationFailed(format!(
+                "No default {:?} device found",
+                scope
+            )));
         }
 
         Ok(device_id)
     }
 
-    /// Get a float property from the default output device (channel 0 = master).
+    /// Get a float property from the controlled device (channel 0 = master).
     fn get_float_property(&self, selector: u32) -> Result<f32, AudioControlError> {
         let address = AudioObjectPropertyAddress {
             mSelector: selector,
-            mScope: kAudioDevicePropertyScopeOutput,
+            mScope: self.watch.scope.property_scope(),
             mElement: kAudioObjectPropertyElementMain, // Channel 0 = master
         };
 
         let mut value: f32 = 0.0;
         let mut size = std::mem::size_of::<f32>() as u32;
+        let device_id = self.active_device_id()?;
 
         let status = unsafe {
             AudioObjectGetPropertyData(
-                self.device_id,
+                device_id,
                 NonNull::new(&address as *const _ as *mut _).unwrap(),
                 0,
                 std::ptr::null(),
@@ -101,19 +306,20 @@ impl MacOSAudioController {
         Ok(value)
     }
 
-    /// Set a float property on the default output device (channel 0 = master).
+    /// Set a float property on the controlled device (channel 0 = master).
     fn set_float_property(&self, selector: u32, value: f32) -> Result<(), AudioControlError> {
         let address = AudioObjectPropertyAddress {
             mSelector: selector,
-            mScope: kAudioDevicePropertyScopeOutput,
+            mScope: self.watch.scope.property_scope(),
             mElement: kAudioObjectPropertyElementMain, // Channel 0 = master
         };
 
         let size = std::mem::size_of::<f32>() as u32;
+        let device_id = self.active_device_id()?;
 
         let status = unsafe {
             AudioObjectSetPropertyData(
-                self.device_id,
+                device_id,
                 NonNull::new(&address as *const _ as *mut _).unwrap(),
                 0,
                 std::ptr::null(),
@@ -132,20 +338,21 @@ impl MacOSAudioController {
         Ok(())
     }
 
-    /// Get a u32 property from the default output device.
+    /// Get a u32 property from the controlled device.
     fn get_u32_property(&self, selector: u32) -> Result<u32, AudioControlError> {
         let address = AudioObjectPropertyAddress {
             mSelector: selector,
-            mScope: kAudioDevicePropertyScopeOutput,
+            mScope: self.watch.scope.property_scope(),
             mElement: kAudioObjectPropertyElementMain,
         };
 
         let mut value: u32 = 0;
         let mut size = std::mem::size_of::<u32>() as u32;
+        let device_id = self.active_device_id()?;
 
         let status = unsafe {
             AudioObjectGetPropertyData(
-                self.device_id,
+                device_id,
                 NonNull::new(&address as *const _ as *mut _).unwrap(),
                 0,
                 std::ptr::null(),
@@ -164,19 +371,20 @@ impl MacOSAudioController {
         Ok(value)
     }
 
-    /// Set a u32 property on the default output device.
+    /// Set a u32 property on the controlled device.
     fn set_u32_property(&self, selector: u32, value: u32) -> Result<(), AudioControlError> {
         let address = AudioObjectPropertyAddress {
             mSelector: selector,
-            mScope: kAudioDevicePropertyScopeOutput,
+            mScope: self.watch.scope.property_scope(),
             mElement: kAudioObjectPropertyElementMain,
         };
 
         let size = std::mem::size_of::<u32>() as u32;
+        let device_id = self.active_device_id()?;
 
         let status = unsafe {
             AudioObjectSetPropertyData(
-                self.device_id,
+                device_id,
                 NonNull::new(&address as *const _ as *mut _).unwrap(),
                 0,
                 std::ptr::null(),
@@ -214,4 +422,201 @@ impl SystemAudioControl for MacOSAudioController {
     fn set_muted(&self, muted: bool) -> Result<(), AudioControlError> {
         self.set_u32_property(kAudioDevicePropertyMute, if muted { 1 } else { 0 })
     }
+
+    fn refresh_device(&self) {
+        // A pinned device is never replaced by whatever is currently default.
+        if self.watch.pinned {
+            return;
+        }
+        match Self::get_default_device(self.watch.scope) {
+            Ok(device_id) => self.watch.device_id.store(device_id, Ordering::SeqCst),
+            Err(e) => log::warn!("Failed to refresh default device: {}", e),
+        }
+    }
+}
+
+impl Drop for MacOSAudioController {
+    fn drop(&mut self) {
+        if !self.listener_registered {
+            return; // Nothing was registered, so there's no Arc clone to reclaim.
+        }
+
+        let address = self.watch.scope.default_device_address();
+        let status = unsafe {
+            AudioObjectRemovePropertyListener(
+                kAudioObjectSystemObject,
+                NonNull::new(&address as *const _ as *mut _).unwrap(),
+                Some(default_device_listener),
+                Arc::as_ptr(&self.watch) as *mut c_void,
+            )
+        };
+        if status != 0 {
+            log::warn!(
+                "Failed to remove default device listener (OSStatus: {})",
+                status
+            );
+        }
+
+        // Reclaim the Arc clone handed to the listener as client data so its
+        // refcount drops back to zero instead of leaking.
+        unsafe { drop(Arc::from_raw(Arc::as_ptr(&self.watch))) };
+    }
+}
+
+/// Read an audio device's name via `kAudioDevicePropertyDeviceNameCFString`.
+fn get_device_name(device_id: u32) -> Result<String, AudioControlError> {
+    let address = AudioObjectPropertyAddress {
+        mSelector: kAudioDevicePropertyDeviceNameCFString,
+        mScope: kAudioObjectPropertyScopeGlobal,
+        mElement: kAudioObjectPropertyElementMain,
+    };
+
+    let mut cf_string_ref: *const c_void = std::ptr::null();
+    let mut size = std::mem::size_of::<*const c_void>() as u32;
+
+    let status = unsafe {
+        AudioObjectGetPropertyData(
+            device_id,
+            NonNull::new(&address as *const _ as *mut _).unwrap(),
+            0,
+            std::ptr::null(),
+            NonNull::new(&mut size as *mut _).unwrap(),
+            NonNull::new(&mut cf_string_ref as *mut _ as *mut c_void).unwrap(),
+        )
+    };
+
+    if status != 0 || cf_string_ref.is_null() {
+        return Err(AudioControlError::GetPropertyFailed(format!(
+            "Failed to get device name (OSStatus: {})",
+            status
+        )));
+    }
+
+    let mut buf = vec![0u8; 512];
+    let decoded = unsafe {
+        CFStringGetCString(
+            cf_string_ref,
+            buf.as_mut_ptr() as *mut i8,
+            buf.len() as isize,
+            K_CF_STRING_ENCODING_UTF8,
+        )
+    };
+    unsafe { CFRelease(cf_string_ref) };
+
+    if decoded == 0 {
+        return Err(AudioControlError::GetPropertyFailed(
+            "Failed to decode device name".to_string(),
+        ));
+    }
+
+    let nul = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    Ok(String::from_utf8_lossy(&buf[..nul]).into_owned())
+}
+
+/// Whether a device has at least one channel in the given property scope
+/// (`kAudioDevicePropertyScopeOutput`/`...ScopeInput`), per
+/// `kAudioDevicePropertyStreamConfiguration`.
+fn device_has_channels(device_id: u32, scope: u32) -> bool {
+    let address = AudioObjectPropertyAddress {
+        mSelector: kAudioDevicePropertyStreamConfiguration,
+        mScope: scope,
+        mElement: kAudioObjectPropertyElementMain,
+    };
+
+    let mut size: u32 = 0;
+    let size_status = unsafe {
+        AudioObjectGetPropertyDataSize(
+            device_id,
+            NonNull::new(&address as *const _ as *mut _).unwrap(),
+            0,
+            std::ptr::null(),
+            NonNull::new(&mut size as *mut _).unwrap(),
+        )
+    };
+    if size_status != 0 || (size as usize) < std::mem::size_of::<AudioBufferList>() {
+        return false;
+    }
+
+    // AudioBufferList ends in a flexible array member (`mBuffers`), so it's
+    // read out of a raw buffer sized to however many buffers CoreAudio reports.
+    let mut raw = vec![0u8; size as usize];
+    let data_status = unsafe {
+        AudioObjectGetPropertyData(
+            device_id,
+            NonNull::new(&address as *const _ as *mut _).unwrap(),
+            0,
+            std::ptr::null(),
+            NonNull::new(&mut size as *mut _).unwrap(),
+            NonNull::new(raw.as_mut_ptr() as *mut c_void).unwrap(),
+        )
+    };
+    if data_status != 0 {
+        return false;
+    }
+
+    let list = raw.as_ptr() as *const AudioBufferList;
+    let num_buffers = unsafe { (*list).mNumberBuffers };
+    let buffers = unsafe { (*list).mBuffers.as_ptr() };
+    (0..num_buffers).any(|i| unsafe { (*buffers.add(i as usize)).mNumberChannels > 0 })
+}
+
+/// Enumerate all known audio devices via `kAudioHardwarePropertyDevices`.
+pub(super) fn list_devices() -> Result<Vec<AudioDevice>, AudioControlError> {
+    let address = AudioObjectPropertyAddress {
+        mSelector: kAudioHardwarePropertyDevices,
+        mScope: kAudioObjectPropertyScopeGlobal,
+        mElement: kAudioObjectPropertyElementMain,
+    };
+
+    let mut size: u32 = 0;
+    let status = unsafe {
+        AudioObjectGetPropertyDataSize(
+            kAudioObjectSystemObject,
+            NonNull::new(&address as *const _ as *mut _).unwrap(),
+            0,
+            std::ptr::null(),
+            NonNull::new(&mut size as *mut _).unwrap(),
+        )
+    };
+    if status != 0 {
+        return Err(AudioControlError::GetPropertyFailed(format!(
+            "Failed to get audio device list size (OSStatus: {})",
+            status
+        )));
+    }
+
+    let count = size as usize / std::mem::size_of::<u32>();
+    let mut ids = vec![0u32; count];
+    let status = unsafe {
+        AudioObjectGetPropertyData(
+            kAudioObjectSystemObject,
+            NonNull::new(&address as *const _ as *mut _).unwrap(),
+            0,
+            std::ptr::null(),
+            NonNull::new(&mut size as *mut _).unwrap(),
+            NonNull::new(ids.as_mut_ptr() as *mut c_void).unwrap(),
+        )
+    };
+    if status != 0 {
+        return Err(AudioControlError::GetPropertyFailed(format!(
+            "Failed to get audio device list (OSStatus: {})",
+            status
+        )));
+    }
+
+    Ok(ids
+        .into_iter()
+        .filter_map(|id| match get_device_name(id) {
+            Ok(name) => Some(AudioDevice {
+                id,
+                name,
+                has_output: device_has_channels(id, kAudioDevicePropertyScopeOutput),
+                has_input: device_has_channels(id, kAudioDevicePropertyScopeInput),
+            }),
+            Err(e) => {
+                log::warn!("Failed to read name for audio device {}: {}", id, e);
+                None
+            }
+        })
+        .collect())
 }
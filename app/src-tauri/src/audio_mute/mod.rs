@@ -5,6 +5,7 @@
 
 use std::fmt;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
 
 // Platform-specific implementations
 #[cfg(target_os = "macos")]
@@ -58,6 +59,13 @@ pub trait SystemAudioControl: Send + Sync {
 
     /// Set system mute state
     fn set_muted(&self, muted: bool) -> Result<(), AudioControlError>;
+
+    /// Re-check the current default device.
+    ///
+    /// Platforms that cache a device id (like macOS) override this to pick
+    /// up changes made outside of a property-change listener, e.g. right
+    /// before muting. The default implementation is a no-op.
+    fn refresh_device(&self) {}
 }
 
 /// Check if audio mute is supported on this platform.
@@ -72,60 +80,201 @@ pub fn is_supported() -> bool {
     }
 }
 
-/// Create a platform-appropriate audio controller.
+/// Which device a [`SystemAudioControl`] operates on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    /// The default input device (microphone).
+    Input,
+    /// The default output device (speakers/headphones).
+    Output,
+}
+
+/// Create a platform-appropriate audio controller for the default output device.
 ///
 /// Returns a boxed trait object that can control system audio.
 /// On unsupported platforms, returns a stub that does nothing.
 pub fn create_controller() -> Result<Box<dyn SystemAudioControl>, AudioControlError> {
+    create_controller_for_scope(Scope::Output)
+}
+
+/// Create a platform-appropriate audio controller for the given [`Scope`].
+///
+/// Returns a boxed trait object that can control system audio.
+/// On unsupported platforms, returns a stub that does nothing.
+pub fn create_controller_for_scope(
+    scope: Scope,
+) -> Result<Box<dyn SystemAudioControl>, AudioControlError> {
+    create_controller_for_device(scope, None)
+}
+
+/// Create a platform-appropriate audio controller for the given [`Scope`],
+/// optionally pinned to a specific device id from [`list_devices`] instead of
+/// always following the default device.
+pub fn create_controller_for_device(
+    scope: Scope,
+    device_id: Option<u32>,
+) -> Result<Box<dyn SystemAudioControl>, AudioControlError> {
     #[cfg(target_os = "windows")]
     {
-        windows::WindowsAudioController::new().map(|c| Box::new(c) as Box<dyn SystemAudioControl>)
+        windows::WindowsAudioController::with_device(scope, device_id)
+            .map(|c| Box::new(c) as Box<dyn SystemAudioControl>)
     }
 
     #[cfg(target_os = "macos")]
     {
-        macos::MacOSAudioController::new().map(|c| Box::new(c) as Box<dyn SystemAudioControl>)
+        macos::MacOSAudioController::with_device(scope, device_id)
+            .map(|c| Box::new(c) as Box<dyn SystemAudioControl>)
     }
 
     #[cfg(not(any(target_os = "windows", target_os = "macos")))]
     {
+        let _ = (scope, device_id);
         Ok(Box::new(stub::StubAudioController::new()))
     }
 }
 
+/// A system audio device discoverable via [`list_devices`].
+#[derive(Debug, Clone)]
+pub struct AudioDevice {
+    /// Platform-specific device id, usable with [`create_controller_for_device`].
+    pub id: u32,
+    /// Human-readable device name (e.g. "MacBook Pro Speakers").
+    pub name: String,
+    /// Whether this device has an output (playback) stream.
+    pub has_output: bool,
+    /// Whether this device has an input (recording) stream.
+    pub has_input: bool,
+}
+
+/// Enumerate all known audio devices.
+///
+/// Returns an empty list on platforms without a device-enumeration API.
+pub fn list_devices() -> Result<Vec<AudioDevice>, AudioControlError> {
+    #[cfg(target_os = "windows")]
+    {
+        windows::list_devices()
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        macos::list_devices()
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        Ok(Vec::new())
+    }
+}
+
+/// How to suppress system audio while recording.
+#[derive(Debug, Clone, Copy)]
+pub enum MuteMode {
+    /// Fully mute the output device.
+    Mute,
+    /// Lower the output volume to `target` (a fraction of the current level)
+    /// instead of muting it outright, so faint audio (e.g. a call) stays audible.
+    Duck {
+        /// Fraction of the current volume to duck to, e.g. `0.2` for 20%.
+        target: f32,
+    },
+}
+
 /// Manages muting/unmuting system audio during recording.
 ///
 /// Tracks whether audio was muted before we started, so we can restore
 /// the correct state after recording ends.
 pub struct AudioMuteManager {
     controller: Box<dyn SystemAudioControl>,
-    /// Was audio already muted before we started muting?
+    mode: MuteMode,
+    /// Optional companion controller for the input device (microphone), used
+    /// to catch the "recorded silence because the mic itself was muted" case.
+    /// Absent if the input device couldn't be opened; that's not fatal.
+    mic_controller: Option<Box<dyn SystemAudioControl>>,
+    /// Was audio already muted before we started? Only the mute flag is
+    /// checked; a volume the user lowered themselves is not detected.
     was_muted_before: AtomicBool,
+    /// Volume level saved before ducking, so we can restore it exactly.
+    saved_volume: Mutex<Option<f32>>,
+    /// Did `check_mic_not_muted` force-unmute the microphone? If so, it was
+    /// muted on purpose (privacy switch, meeting mute) and must be put back
+    /// the way we found it once recording stops.
+    mic_was_unmuted_by_us: AtomicBool,
     /// Are we currently in a muted state (that we caused)?
     is_currently_muting: AtomicBool,
 }
 
 impl AudioMuteManager {
-    /// Create a new AudioMuteManager.
+    /// Create a new AudioMuteManager that fully mutes audio during recording.
     ///
     /// Returns None if audio control is not available on this platform.
     pub fn new() -> Option<Self> {
-        match create_controller() {
-            Ok(controller) => Some(Self {
-                controller,
-                was_muted_before: AtomicBool::new(false),
-                is_currently_muting: AtomicBool::new(false),
-            }),
+        Self::with_mode(MuteMode::Mute)
+    }
+
+    /// Create a new AudioMuteManager using the given [`MuteMode`].
+    ///
+    /// Returns None if audio control is not available on this platform.
+    pub fn with_mode(mode: MuteMode) -> Option<Self> {
+        Self::with_mode_and_device(mode, None)
+    }
+
+    /// Create a new AudioMuteManager using the given [`MuteMode`], controlling
+    /// a specific output device (from [`list_devices`]) instead of whichever
+    /// device is currently the default.
+    pub fn with_mode_and_device(mode: MuteMode, device_id: Option<u32>) -> Option<Self> {
+        let controller = match create_controller_for_device(Scope::Output, device_id) {
+            Ok(controller) => controller,
             Err(e) => {
                 log::warn!("Audio mute not available: {}", e);
+                return None;
+            }
+        };
+
+        let mic_controller = match create_controller_for_scope(Scope::Input) {
+            Ok(mic) => Some(mic),
+            Err(e) => {
+                log::warn!("Microphone mute check not available: {}", e);
                 None
             }
+        };
+
+        Some(Self {
+            controller,
+            mode,
+            mic_controller,
+            was_muted_before: AtomicBool::new(false),
+            saved_volume: Mutex::new(None),
+            mic_was_unmuted_by_us: AtomicBool::new(false),
+            is_currently_muting: AtomicBool::new(false),
+        })
+    }
+
+    /// Warn (and auto-unmute) if the microphone itself is hardware/OS-muted.
+    ///
+    /// Otherwise recording silently captures silence with no other symptom.
+    /// Remembers whether it actually changed anything, so [`Self::unmute`]
+    /// can put the microphone back the way it found it.
+    fn check_mic_not_muted(&self) {
+        let Some(mic) = &self.mic_controller else {
+            return;
+        };
+
+        match mic.is_muted() {
+            Ok(true) => {
+                log::warn!("Microphone is muted; unmuting it so recording can pick up audio");
+                match mic.set_muted(false) {
+                    Ok(()) => self.mic_was_unmuted_by_us.store(true, Ordering::SeqCst),
+                    Err(e) => log::warn!("Failed to unmute microphone: {}", e),
+                }
+            }
+            Ok(false) => {}
+            Err(e) => log::debug!("Could not check microphone mute state: {}", e),
         }
     }
 
-    /// Mute system audio for recording.
+    /// Mute (or duck) system audio for recording.
     ///
-    /// Saves the current mute state so it can be restored later.
+    /// Saves the current mute/volume state so it can be restored later.
     /// If already muting, this is a no-op.
     pub fn mute(&self) -> Result<(), AudioControlError> {
         // Check if we're already muting
@@ -133,24 +282,56 @@ impl AudioMuteManager {
             return Ok(()); // Already muting, nothing to do
         }
 
+        // Pick up device changes (e.g. headphones connected) before acting
+        self.controller.refresh_device();
+
+        // Catch the "recorded silence" failure mode up front
+        self.check_mic_not_muted();
+
         // Check current mute state and save it
         let was_muted = self.controller.is_muted().unwrap_or(false);
         self.was_muted_before.store(was_muted, Ordering::SeqCst);
 
-        // Only mute if not already muted
-        if !was_muted {
-            self.controller.set_muted(true)?;
-            log::info!("System audio muted for recording");
-        } else {
+        // Don't fight a user who already muted audio themselves
+        if was_muted {
             log::info!("System audio already muted, skipping");
+            return Ok(());
+        }
+
+        match self.mode {
+            MuteMode::Mute => {
+                if let Err(e) = self.controller.set_muted(true) {
+                    // Nothing actually changed; don't leave us stuck thinking
+                    // we're muting when a later unmute() would have nothing
+                    // to undo.
+                    self.is_currently_muting.store(false, Ordering::SeqCst);
+                    return Err(e);
+                }
+                log::info!("System audio muted for recording");
+            }
+            MuteMode::Duck { target } => {
+                let current = self.controller.get_volume().unwrap_or(1.0);
+                if let Err(e) = self.controller.set_volume(current * target) {
+                    self.is_currently_muting.store(false, Ordering::SeqCst);
+                    return Err(e);
+                }
+                // Only persist the saved volume once ducking actually
+                // happened, so a failed duck can't leave a stale value for
+                // unmute() to "restore" later.
+                *self.saved_volume.lock().unwrap() = Some(current);
+                log::info!(
+                    "System audio ducked to {:.0}% for recording",
+                    target * 100.0
+                );
+            }
         }
 
         Ok(())
     }
 
-    /// Unmute system audio after recording.
+    /// Unmute (or restore ducked volume) system audio after recording.
     ///
-    /// Only unmutes if we were the ones who muted it.
+    /// Only restores if we were the ones who muted/ducked it.
     /// If not currently muting, this is a no-op.
     pub fn unmute(&self) -> Result<(), AudioControlError> {
         // Check if we're currently muting
@@ -158,16 +339,50 @@ impl AudioMuteManager {
             return Ok(()); // Not muting, nothing to do
         }
 
-        // Only unmute if it wasn't already muted before we started
-        if !self.was_muted_before.load(Ordering::SeqCst) {
-            self.controller.set_muted(false)?;
-            log::info!("System audio unmuted after recording");
-        } else {
+        // Pick up device changes (e.g. headphones connected) before acting
+        self.controller.refresh_device();
+
+        // The mic is a separate device from the output; restore it regardless
+        // of whether output audio needs restoring below.
+        self.restore_mic_mute();
+
+        // Only restore if it wasn't already muted before we started
+        if self.was_muted_before.load(Ordering::SeqCst) {
             log::info!("System audio was already muted, leaving muted");
+            return Ok(());
+        }
+
+        match self.mode {
+            MuteMode::Mute => {
+                self.controller.set_muted(false)?;
+                log::info!("System audio unmuted after recording");
+            }
+            MuteMode::Duck { .. } => {
+                if let Some(level) = self.saved_volume.lock().unwrap().take() {
+                    self.controller.set_volume(level)?;
+                    log::info!("System audio volume restored after recording");
+                }
+            }
         }
 
         Ok(())
     }
+
+    /// Re-mute the microphone if [`Self::check_mic_not_muted`] force-unmuted
+    /// it for this recording, so a deliberately-muted mic isn't left unmuted.
+    fn restore_mic_mute(&self) {
+        if !self.mic_was_unmuted_by_us.swap(false, Ordering::SeqCst) {
+            return;
+        }
+
+        let Some(mic) = &self.mic_controller else {
+            return;
+        };
+
+        if let Err(e) = mic.set_muted(true) {
+            log::warn!("Failed to restore microphone mute state: {}", e);
+        }
+    }
 }
 
 impl Drop for AudioMuteManager {
@@ -178,3 +393,195 @@ impl Drop for AudioMuteManager {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    /// Shared, inspectable state behind a [`FakeAudioControl`].
+    #[derive(Default)]
+    struct FakeState {
+        volume: Mutex<f32>,
+        muted: Mutex<bool>,
+    }
+
+    /// In-memory [`SystemAudioControl`] for exercising `AudioMuteManager`
+    /// without touching any real hardware.
+    struct FakeAudioControl(Arc<FakeState>);
+
+    impl SystemAudioControl for FakeAudioControl {
+        fn get_volume(&self) -> Result<f32, AudioControlError> {
+            Ok(*self.0.volume.lock().unwrap())
+        }
+
+        fn set_volume(&self, level: f32) -> Result<(), AudioControlError> {
+            *self.0.volume.lock().unwrap() = level;
+            Ok(())
+        }
+
+        fn is_muted(&self) -> Result<bool, AudioControlError> {
+            Ok(*self.0.muted.lock().unwrap())
+        }
+
+        fn set_muted(&self, muted: bool) -> Result<(), AudioControlError> {
+            *self.0.muted.lock().unwrap() = muted;
+            Ok(())
+        }
+    }
+
+    /// [`SystemAudioControl`] that always fails writes, for exercising
+    /// `mute()`'s error paths.
+    struct FailingAudioControl(Arc<FakeState>);
+
+    impl SystemAudioControl for FailingAudioControl {
+        fn get_volume(&self) -> Result<f32, AudioControlError> {
+            Ok(*self.0.volume.lock().unwrap())
+        }
+
+        fn set_volume(&self, _level: f32) -> Result<(), AudioControlError> {
+            Err(AudioControlError::SetPropertyFailed("simulated failure".into()))
+        }
+
+        fn is_muted(&self) -> Result<bool, AudioControlError> {
+            Ok(*self.0.muted.lock().unwrap())
+        }
+
+        fn set_muted(&self, _muted: bool) -> Result<(), AudioControlError> {
+            Err(AudioControlError::SetPropertyFailed("simulated failure".into()))
+        }
+    }
+
+    fn fake_state(volume: f32, muted: bool) -> Arc<FakeState> {
+        Arc::new(FakeState {
+            volume: Mutex::new(volume),
+            muted: Mutex::new(muted),
+        })
+    }
+
+    fn manager(
+        mode: MuteMode,
+        state: &Arc<FakeState>,
+        mic_state: Option<&Arc<FakeState>>,
+    ) -> AudioMuteManager {
+        let mic_controller = mic_state
+            .map(|s| Box::new(FakeAudioControl(Arc::clone(s))) as Box<dyn SystemAudioControl>);
+
+        AudioMuteManager {
+            controller: Box::new(FakeAudioControl(Arc::clone(state))),
+            mode,
+            mic_controller,
+            was_muted_before: AtomicBool::new(false),
+            saved_volume: Mutex::new(None),
+            mic_was_unmuted_by_us: AtomicBool::new(false),
+            is_currently_muting: AtomicBool::new(false),
+        }
+    }
+
+    #[test]
+    fn duck_mode_saves_and_restores_volume() {
+        let state = fake_state(0.8, false);
+        let mgr = manager(MuteMode::Duck { target: 0.25 }, &state, None);
+
+        mgr.mute().unwrap();
+        assert!((*state.volume.lock().unwrap() - 0.2).abs() < f32::EPSILON);
+
+        mgr.unmute().unwrap();
+        assert!((*state.volume.lock().unwrap() - 0.8).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn duck_mode_does_not_leave_stale_state_when_set_volume_fails() {
+        let state = fake_state(0.8, false);
+        let mgr = AudioMuteManager {
+            controller: Box::new(FailingAudioControl(Arc::clone(&state))),
+            mode: MuteMode::Duck { target: 0.25 },
+            mic_controller: None,
+            was_muted_before: AtomicBool::new(false),
+            saved_volume: Mutex::new(None),
+            mic_was_unmuted_by_us: AtomicBool::new(false),
+            is_currently_muting: AtomicBool::new(false),
+        };
+
+        assert!(mgr.mute().is_err());
+
+        // Nothing was actually ducked, so there must be nothing to restore,
+        // and a later mute() attempt must not think we're already muting.
+        assert!(mgr.saved_volume.lock().unwrap().is_none());
+        assert!(!mgr.is_currently_muting.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn mute_does_not_fight_audio_already_muted() {
+        let state = fake_state(1.0, true);
+        let mgr = manager(MuteMode::Mute, &state, None);
+
+        mgr.mute().unwrap();
+        mgr.unmute().unwrap();
+
+        // Was already muted before we started, so we must leave it muted.
+        assert!(*state.muted.lock().unwrap());
+    }
+
+    #[test]
+    fn duck_mode_does_not_fight_audio_already_muted() {
+        let state = fake_state(0.5, true);
+        let mgr = manager(MuteMode::Duck { target: 0.25 }, &state, None);
+
+        mgr.mute().unwrap();
+        // We must not have touched volume since the user had already muted.
+        assert!((*state.volume.lock().unwrap() - 0.5).abs() < f32::EPSILON);
+
+        mgr.unmute().unwrap();
+        assert!(*state.muted.lock().unwrap());
+    }
+
+    #[test]
+    fn check_mic_not_muted_auto_unmutes_a_muted_microphone() {
+        let output_state = fake_state(1.0, false);
+        let mic_state = fake_state(1.0, true);
+        let mgr = manager(MuteMode::Mute, &output_state, Some(&mic_state));
+
+        mgr.check_mic_not_muted();
+
+        assert!(!*mic_state.muted.lock().unwrap());
+    }
+
+    #[test]
+    fn check_mic_not_muted_leaves_an_unmuted_microphone_alone() {
+        let output_state = fake_state(1.0, false);
+        let mic_state = fake_state(1.0, false);
+        let mgr = manager(MuteMode::Mute, &output_state, Some(&mic_state));
+
+        mgr.check_mic_not_muted();
+
+        assert!(!*mic_state.muted.lock().unwrap());
+    }
+
+    #[test]
+    fn unmute_restores_a_microphone_that_was_deliberately_muted() {
+        let output_state = fake_state(1.0, false);
+        let mic_state = fake_state(1.0, true);
+        let mgr = manager(MuteMode::Mute, &output_state, Some(&mic_state));
+
+        mgr.mute().unwrap();
+        // The mic should have been force-unmuted so recording can hear it.
+        assert!(!*mic_state.muted.lock().unwrap());
+
+        mgr.unmute().unwrap();
+        // The user had it muted on purpose; put it back the way we found it.
+        assert!(*mic_state.muted.lock().unwrap());
+    }
+
+    #[test]
+    fn unmute_leaves_an_already_unmuted_microphone_alone() {
+        let output_state = fake_state(1.0, false);
+        let mic_state = fake_state(1.0, false);
+        let mgr = manager(MuteMode::Mute, &output_state, Some(&mic_state));
+
+        mgr.mute().unwrap();
+        mgr.unmute().unwrap();
+
+        assert!(!*mic_state.muted.lock().unwrap());
+    }
+}